@@ -2,12 +2,108 @@ use crate::settings::TokenType;
 use crate::trie::{Trie, TrieResult};
 use crate::{Token, TokenTypeSettings, TokenizerDialectSettings, TokenizerSettings};
 use pyo3::prelude::*;
-use std::cmp::{max, min};
 
+/// A byte-offset range (`start..end`) identifying exactly what text a
+/// diagnostic is about, plus a line/column. `line` and `column` are those of
+/// the cursor at the time the span was taken (the `end` side of the range,
+/// not `start`), matching the bookkeeping `advance()` already maintains.
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A lexical error, typed so downstream parser/LSP code can match on the
+/// kind of failure instead of pattern-matching a formatted string. Every
+/// variant carries the failure's `span` and the surrounding ±50-char
+/// `context` window that used to live on a single untyped struct.
 #[derive(Debug)]
-pub struct TokenizerError {
-    message: String,
-    context: String,
+pub enum TokenizerError {
+    /// A delimited string or quoted identifier was opened but the input
+    /// ended before its closing `delimiter` was found.
+    UnterminatedDelimiter {
+        delimiter: String,
+        span: Span,
+        context: String,
+    },
+    /// Scanning needed another character (e.g. to complete a two-char
+    /// escape or look up the next byte) but the input had already ended.
+    UnexpectedEof { span: Span, context: String },
+    /// A quoted hex or bit string (`x'..'`, `b'..'`) contained a character
+    /// that isn't a digit of its base.
+    InvalidNumber {
+        text: String,
+        span: Span,
+        context: String,
+    },
+    /// A `\`-escape (C-style, hex, Unicode, or octal) was malformed:
+    /// unknown introducer, bad digit, or an out-of-range/surrogate value.
+    InvalidEscape {
+        reason: String,
+        span: Span,
+        context: String,
+    },
+    /// A quoted identifier failed a validity check (e.g. it was empty).
+    InvalidIdentifier {
+        reason: String,
+        span: Span,
+        context: String,
+    },
+    /// Anything else - an internal invariant violation (settings/trie
+    /// mismatch, stepping back past the start of input), or a Unicode
+    /// lookalike-character diagnostic reported by `tokenize_recover`.
+    Other {
+        message: String,
+        span: Span,
+        context: String,
+    },
+}
+
+impl std::fmt::Display for TokenizerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (message, span, context) = match self {
+            TokenizerError::UnterminatedDelimiter {
+                delimiter,
+                span,
+                context,
+            } => (format!("Missing {}", delimiter), *span, context),
+            TokenizerError::UnexpectedEof { span, context } => {
+                ("Unexpected end of input".to_string(), *span, context)
+            }
+            TokenizerError::InvalidNumber {
+                text,
+                span,
+                context,
+            } => (
+                format!("'{}' is not a valid number literal", text),
+                *span,
+                context,
+            ),
+            TokenizerError::InvalidEscape {
+                reason,
+                span,
+                context,
+            } => (reason.clone(), *span, context),
+            TokenizerError::InvalidIdentifier {
+                reason,
+                span,
+                context,
+            } => (reason.clone(), *span, context),
+            TokenizerError::Other {
+                message,
+                span,
+                context,
+            } => (message.clone(), *span, context),
+        };
+
+        write!(
+            f,
+            "Error tokenizing '{}' at {}:{}: {}",
+            context, span.line, span.column, message
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -32,6 +128,7 @@ impl Tokenizer {
         keyword_trie.add(settings.comments.keys().filter(trie_filter));
         keyword_trie.add(settings.quotes.keys().filter(trie_filter));
         keyword_trie.add(settings.format_strings.keys().filter(trie_filter));
+        keyword_trie.add(settings.string_prefixes.keys().filter(trie_filter));
 
         Tokenizer {
             settings,
@@ -55,21 +152,250 @@ impl Tokenizer {
         let tokenize_result = state.tokenize();
         match tokenize_result {
             Ok(tokens) => (tokens, None),
-            Err(e) => {
-                let msg = format!("Error tokenizing '{}': {}", e.context, e.message);
-                (state.tokens, Some(msg))
+            Err(e) => (state.tokens, Some(e.to_string())),
+        }
+    }
+
+    /// Like `tokenize`, but never aborts on the first lexical error. Instead it
+    /// records each error, resynchronizes at the next whitespace or
+    /// single-token character, and keeps scanning, returning every diagnostic
+    /// collected along the way. Intended for editor/LSP callers that want to
+    /// report multiple errors from a single pass rather than one per run.
+    pub fn tokenize_recover(
+        &self,
+        sql: &str,
+        dialect_settings: &TokenizerDialectSettings,
+    ) -> (Vec<Token>, Vec<String>) {
+        let mut state = TokenizerState::new(
+            sql,
+            &self.settings,
+            &self.token_types,
+            dialect_settings,
+            &self.keyword_trie,
+        );
+        let errors = state.tokenize_recover();
+        (state.tokens, errors.iter().map(|e| e.to_string()).collect())
+    }
+
+    /// Re-tokenizes `new_sql` given that it was produced from `old_sql` by
+    /// replacing the byte range `[edit_start, edit_end)`, reusing as much of
+    /// `old_tokens` as possible instead of rescanning the whole input.
+    ///
+    /// Every token fully before `edit_start` is reused verbatim: its span
+    /// (and whatever multi-line string/comment it may have swallowed) lies
+    /// entirely outside the edit, no matter how far that token's own scan
+    /// reached. Scanning resumes from the *start* of the last such token
+    /// rather than from `edit_start` itself, so we never restart in the
+    /// middle of a region `extract_string`/`scan_comment` is still tracking -
+    /// that token itself is dropped from `prefix` and left to the fresh scan
+    /// to re-emit, since rescanning starts on top of it. Once the fresh scan
+    /// produces a token whose type and text match an old token at the
+    /// edit-shifted offset, we splice in the (offset-adjusted) remainder of
+    /// `old_tokens` instead of rescanning it.
+    ///
+    /// The fresh scan itself starts from a small window just past the edit
+    /// and only widens (doubling, up to the whole remainder) if nothing
+    /// reconverges inside it, so a small edit deep in a large document stays
+    /// proportional to the edit rather than rescanning to EOF every time.
+    pub fn retokenize(
+        &self,
+        old_tokens: Vec<Token>,
+        old_sql: &str,
+        new_sql: &str,
+        edit_start: usize,
+        edit_end: usize,
+        dialect_settings: &TokenizerDialectSettings,
+    ) -> Vec<Token> {
+        const MIN_LOOKAHEAD: usize = 256;
+
+        let delta = new_sql.len() as isize - old_sql.len() as isize;
+        let new_edit_end = (edit_end as isize + delta) as usize;
+
+        // Line/column at `edit_end` in both texts, so the reused suffix
+        // tokens (spliced in verbatim below) can be corrected for any
+        // newlines the edit added or removed, not just the byte shift.
+        let (old_edit_end_line, old_edit_end_column) = line_col_at(old_sql, edit_end);
+        let (new_edit_end_line, new_edit_end_column) = line_col_at(new_sql, new_edit_end);
+        let line_delta = new_edit_end_line as isize - old_edit_end_line as isize;
+        let column_delta = new_edit_end_column as isize - old_edit_end_column as isize;
+
+        let prefix_len = old_tokens.iter().take_while(|t| t.end < edit_start).count();
+        let restart = if prefix_len > 0 {
+            old_tokens[prefix_len - 1].start
+        } else {
+            0
+        };
+        // The token at `prefix_len - 1` (if any) is where rescanning
+        // restarts, so it must not also be kept in `prefix` - otherwise it's
+        // emitted twice, once here and once by `fresh_tail`.
+        let prefix = old_tokens[..prefix_len.saturating_sub(1)].to_vec();
+
+        let suffix_start = old_tokens
+            .iter()
+            .position(|t| t.start >= edit_end)
+            .unwrap_or(old_tokens.len());
+
+        // `fresh_tail` is scanned from a `TokenizerState` that starts its own
+        // count at line 1, column 0, so its tokens' line/column are relative
+        // to `restart`, not absolute. Fold in the line/column `restart`
+        // actually falls at: every fresh line number shifts by the same
+        // amount, but only the *first* fresh line shares `restart`'s column -
+        // once a token has crossed a newline of its own, its column is
+        // already correct (it counts from that newline, same as `restart`
+        // counts from whichever newline precedes it).
+        let (restart_line, restart_column) = line_col_at(new_sql, restart);
+
+        let mut window_end = new_edit_end.saturating_add(MIN_LOOKAHEAD).min(new_sql.len());
+        loop {
+            let reached_end = window_end >= new_sql.len();
+
+            let (fresh_tail, _) = self.tokenize(&new_sql[restart..window_end], dialect_settings);
+            let fresh_tail: Vec<Token> = fresh_tail
+                .into_iter()
+                .map(|mut t| {
+                    if t.line == 1 {
+                        t.col += restart_column;
+                    }
+                    t.line += restart_line - 1;
+                    t.start += restart;
+                    t.end += restart;
+                    t
+                })
+                .collect();
+
+            for (fresh_idx, fresh) in fresh_tail.iter().enumerate() {
+                if fresh.start < new_edit_end {
+                    continue;
+                }
+
+                let reconverged = old_tokens[suffix_start..].iter().position(|old| {
+                    (old.start as isize + delta) as usize == fresh.start
+                        && old.token_type == fresh.token_type
+                        && old.text == fresh.text
+                });
+
+                if let Some(old_idx) = reconverged {
+                    let mut result = prefix;
+                    result.extend(fresh_tail[..fresh_idx].iter().cloned());
+                    result.extend(old_tokens[suffix_start + old_idx..].iter().cloned().map(
+                        |mut t| {
+                            // Tokens still on the edit's line inherit its
+                            // column shift too; tokens on later lines only
+                            // need the line shift, since their column counts
+                            // from a newline that lies entirely outside the
+                            // edit and so is unaffected by it.
+                            if t.line == old_edit_end_line {
+                                t.col = (t.col as isize + column_delta) as usize;
+                            }
+                            t.line = (t.line as isize + line_delta) as usize;
+                            t.start = (t.start as isize + delta) as usize;
+                            t.end = (t.end as isize + delta) as usize;
+                            t
+                        },
+                    ));
+                    return result;
+                }
             }
+
+            if reached_end {
+                // Nothing downstream re-converged (e.g. the edit changed
+                // everything after it) - fall back to the freshly scanned
+                // remainder.
+                let mut result = prefix;
+                result.extend(fresh_tail);
+                return result;
+            }
+
+            window_end = new_edit_end
+                .saturating_add((window_end - new_edit_end).saturating_mul(2))
+                .min(new_sql.len());
         }
     }
 }
 
+/// The 1-based line and 0-based column (chars since the preceding newline,
+/// or since the start of `s` if there is none) at byte offset `offset` in
+/// `s`. Mirrors the bookkeeping `TokenizerState::advance` does incrementally,
+/// so it can be used to re-derive absolute positions for tokens that were
+/// scanned, or are being reused, from some offset other than byte 0.
+fn line_col_at(s: &str, offset: usize) -> (usize, usize) {
+    let prefix = &s[..offset];
+    let line = 1 + prefix.matches('\n').count();
+    let column = match prefix.rfind('\n') {
+        Some(newline_pos) => prefix[newline_pos + 1..].chars().count(),
+        None => prefix.chars().count(),
+    };
+    (line, column)
+}
+
+/// Unicode characters that are visually indistinguishable (or close enough)
+/// from an ASCII character SQL assigns meaning to, paired with the ASCII
+/// character they're commonly mistaken for. Modeled on rustc's
+/// `unicode_chars` confusable table; extend as new lookalikes come up.
+static CONFUSABLES: &[(char, char)] = &[
+    ('\u{FF08}', '('),  // FULLWIDTH LEFT PARENTHESIS
+    ('\u{FF09}', ')'),  // FULLWIDTH RIGHT PARENTHESIS
+    ('\u{FF0C}', ','),  // FULLWIDTH COMMA
+    ('\u{FF1B}', ';'),  // FULLWIDTH SEMICOLON
+    ('\u{037E}', ';'),  // GREEK QUESTION MARK
+    ('\u{2018}', '\''), // LEFT SINGLE QUOTATION MARK
+    ('\u{2019}', '\''), // RIGHT SINGLE QUOTATION MARK
+    ('\u{201C}', '"'),  // LEFT DOUBLE QUOTATION MARK
+    ('\u{201D}', '"'),  // RIGHT DOUBLE QUOTATION MARK
+    ('\u{2013}', '-'),  // EN DASH
+    ('\u{2014}', '-'),  // EM DASH
+];
+
+fn confusable_ascii_for(c: char) -> Option<char> {
+    CONFUSABLES
+        .iter()
+        .find(|&&(confusable, _)| confusable == c)
+        .map(|&(_, ascii)| ascii)
+}
+
+/// A cheap, `Copy`-able cursor over the unconsumed tail of the original SQL
+/// text, modeled on `proc_macro2`'s `Cursor`.
+///
+/// Rather than indexing into a pre-collected `Vec<char>` (which allocates
+/// and copies the whole input up front), we keep a `&str` slice of what's
+/// left to scan plus the byte offset it starts at, and advance it by
+/// re-slicing. `rest` doubles as a one-character-lookahead cache: callers
+/// that only need to test a prefix (`starts_with`) never have to decode a
+/// full `char`.
+#[derive(Debug, Clone, Copy)]
+struct Cursor<'a> {
+    rest: &'a str,
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(sql: &'a str) -> Cursor<'a> {
+        Cursor {
+            rest: sql,
+            offset: 0,
+        }
+    }
+
+    fn starts_with(&self, needle: &str) -> bool {
+        self.rest.starts_with(needle)
+    }
+
+    fn advance(&mut self, bytes: usize) {
+        self.rest = &self.rest[bytes..];
+        self.offset += bytes;
+    }
+}
+
 #[derive(Debug)]
 struct TokenizerState<'a> {
-    sql: Vec<char>,
+    sql: &'a str,
     size: usize,
     tokens: Vec<Token>,
     start: usize,
-    current: usize,
+    cursor: Cursor<'a>,
+    // Byte offset at which `current_char` begins; `cursor.offset` is one
+    // past it, i.e. where `peek_char` begins.
+    current_char_start: usize,
     line: usize,
     column: usize,
     comments: Vec<String>,
@@ -77,6 +403,14 @@ struct TokenizerState<'a> {
     current_char: char,
     peek_char: char,
     previous_token_line: Option<usize>,
+    // Set for the lifetime of a `tokenize_recover` pass; lets a scan step
+    // that hits a recoverable issue (e.g. a confusable character) choose to
+    // emit a diagnostic and keep going instead of substituting silently.
+    recovering: bool,
+    // Diagnostics flagged mid-token while `recovering` (e.g. a malformed
+    // `\x`/`\u` escape) that don't otherwise abort the current scan step.
+    // Drained into `tokenize_recover`'s return value once scanning finishes.
+    recovered_errors: Vec<TokenizerError>,
     keyword_trie: &'a Trie,
     settings: &'a TokenizerSettings,
     dialect_settings: &'a TokenizerDialectSettings,
@@ -85,20 +419,19 @@ struct TokenizerState<'a> {
 
 impl<'a> TokenizerState<'a> {
     fn new(
-        sql: &str,
+        sql: &'a str,
         settings: &'a TokenizerSettings,
         token_types: &'a TokenTypeSettings,
         dialect_settings: &'a TokenizerDialectSettings,
         keyword_trie: &'a Trie,
     ) -> TokenizerState<'a> {
-        let sql_vec = sql.chars().collect::<Vec<char>>();
-        let sql_vec_len = sql_vec.len();
         TokenizerState {
-            sql: sql_vec,
-            size: sql_vec_len,
+            sql,
+            size: sql.len(),
             tokens: Vec::new(),
             start: 0,
-            current: 0,
+            cursor: Cursor::new(sql),
+            current_char_start: 0,
             line: 1,
             column: 0,
             comments: Vec::new(),
@@ -106,6 +439,8 @@ impl<'a> TokenizerState<'a> {
             current_char: '\0',
             peek_char: '\0',
             previous_token_line: None,
+            recovering: false,
+            recovered_errors: Vec::new(),
             keyword_trie,
             settings,
             dialect_settings,
@@ -118,23 +453,61 @@ impl<'a> TokenizerState<'a> {
         Ok(std::mem::take(&mut self.tokens))
     }
 
+    fn tokenize_recover(&mut self) -> Vec<TokenizerError> {
+        self.recovering = true;
+        let mut errors = Vec::new();
+
+        while self.size > 0 && !self.is_end {
+            let offset_before_error = self.cursor.offset;
+            if let Err(e) = self.scan(None) {
+                errors.push(e);
+                self.resynchronize();
+                // Neither the failed scan nor `resynchronize` moved the
+                // cursor (e.g. the failure happened right at EOF) - stop
+                // instead of re-scanning the same position forever.
+                if self.cursor.offset == offset_before_error {
+                    break;
+                }
+            }
+        }
+
+        errors.append(&mut self.recovered_errors);
+        errors
+    }
+
+    // The character that caused the current error is already consumed
+    // (`current_char`); keeps skipping until the next whitespace or
+    // single-token character (the same stop conditions `extract_value` uses,
+    // tested on `peek_char`), so `tokenize_recover` doesn't immediately
+    // re-trip on the same input and the stop character itself is scanned next.
+    fn resynchronize(&mut self) {
+        while !self.is_end
+            && !self.peek_char.is_whitespace()
+            && !self.settings.single_tokens.contains_key(&self.peek_char)
+        {
+            if self.advance(1).is_err() {
+                return;
+            }
+        }
+    }
+
     fn scan(&mut self, until_peek_char: Option<char>) -> Result<(), TokenizerError> {
         while self.size > 0 && !self.is_end {
-            let mut current = self.current;
+            let mut current = self.cursor.offset;
 
             // Skip spaces here rather than iteratively calling advance() for performance reasons
             while current < self.size {
                 let ch = self.char_at(current)?;
 
                 if ch == ' ' || ch == '\t' {
-                    current += 1;
+                    current += ch.len_utf8();
                 } else {
                     break;
                 }
             }
 
-            let offset = if current > self.current {
-                current - self.current
+            let offset = if current > self.cursor.offset {
+                current - self.cursor.offset
             } else {
                 1
             };
@@ -184,38 +557,67 @@ impl<'a> TokenizerState<'a> {
             self.column = self.column.wrapping_add_signed(i);
         }
 
-        self.current = self.current.wrapping_add_signed(i);
-        self.is_end = self.current >= self.size;
-        self.current_char = self.char_at(self.current - 1)?;
+        if i >= 0 {
+            for _ in 0..i {
+                self.step_forward()?;
+            }
+        } else {
+            for _ in 0..i.unsigned_abs() {
+                self.step_backward()?;
+            }
+        }
+        Ok(())
+    }
+
+    // Consumes one `char` worth of bytes off the cursor, the hot path for
+    // every token scanned.
+    fn step_forward(&mut self) -> Result<(), TokenizerError> {
+        self.current_char_start = self.cursor.offset;
+        self.current_char = self.char_at(self.cursor.offset)?;
+        self.cursor.advance(self.current_char.len_utf8());
+        self.is_end = self.cursor.offset >= self.size;
         self.peek_char = if self.is_end {
             '\0'
         } else {
-            self.char_at(self.current)?
+            self.char_at(self.cursor.offset)?
         };
         Ok(())
     }
 
-    fn chars(&self, size: usize) -> String {
-        let start = self.current - 1;
-        let end = start + size;
-        if end <= self.size {
-            self.sql[start..end].iter().collect()
+    // Un-consumes one `char`, re-deriving it from the original `&str` since
+    // the cursor can't walk itself backwards.
+    fn step_backward(&mut self) -> Result<(), TokenizerError> {
+        let previous_char = self.sql[..self.current_char_start]
+            .chars()
+            .next_back()
+            .ok_or_else(|| {
+                self.error_other("Cannot step back past the start of input".to_string())
+            })?;
+
+        self.cursor = Cursor {
+            rest: &self.sql[self.current_char_start..],
+            offset: self.current_char_start,
+        };
+        self.current_char = previous_char;
+        self.current_char_start -= previous_char.len_utf8();
+        self.is_end = self.cursor.offset >= self.size;
+        self.peek_char = if self.is_end {
+            '\0'
         } else {
-            String::new()
-        }
+            self.char_at(self.cursor.offset)?
+        };
+        Ok(())
     }
 
     fn char_at(&self, index: usize) -> Result<char, TokenizerError> {
-        self.sql.get(index).copied().ok_or_else(|| {
-            self.error(format!(
-                "Index {} is out of bound (size {})",
-                index, self.size
-            ))
-        })
+        self.sql[index..]
+            .chars()
+            .next()
+            .ok_or_else(|| self.error_unexpected_eof())
     }
 
     fn text(&self) -> String {
-        self.sql[self.start..self.current].iter().collect()
+        self.sql[self.start..self.cursor.offset].to_string()
     }
 
     fn add(&mut self, token_type: TokenType, text: Option<String>) -> Result<(), TokenizerError> {
@@ -237,7 +639,7 @@ impl<'a> TokenizerState<'a> {
             self.line,
             self.column,
             self.start,
-            self.current - 1,
+            self.cursor.offset,
             std::mem::take(&mut self.comments),
         ));
 
@@ -251,15 +653,11 @@ impl<'a> TokenizerState<'a> {
                     .command_prefix_tokens
                     .contains(&self.tokens[self.tokens.len() - 2].token_type))
         {
-            let start = self.current;
+            let start = self.cursor.offset;
             let tokens_len = self.tokens.len();
             self.scan(Some(';'))?;
             self.tokens.truncate(tokens_len);
-            let text = self.sql[start..self.current]
-                .iter()
-                .collect::<String>()
-                .trim()
-                .to_string();
+            let text = self.sql[start..self.cursor.offset].trim().to_string();
             if !text.is_empty() {
                 self.add(self.token_types.string, Some(text))?;
             }
@@ -269,6 +667,7 @@ impl<'a> TokenizerState<'a> {
 
     fn scan_keyword(&mut self) -> Result<(), TokenizerError> {
         let mut size: usize = 0;
+        let mut end = self.cursor.offset;
         let mut word: Option<String> = None;
         let mut chars = self.text();
         let mut current_char = '\0';
@@ -290,11 +689,11 @@ impl<'a> TokenizerState<'a> {
                 word = Some(chars.clone());
             }
 
-            let end = self.current + size;
             size += 1;
 
             if end < self.size {
                 current_char = self.char_at(end)?;
+                end += current_char.len_utf8();
                 is_single_token =
                     is_single_token || self.settings.single_tokens.contains_key(&current_char);
                 let is_space = current_char.is_whitespace();
@@ -338,7 +737,7 @@ impl<'a> TokenizerState<'a> {
                         .keywords
                         .get(&normalized_word)
                         .ok_or_else(|| {
-                            self.error(format!("Unexpected keyword '{}'", &normalized_word))
+                            self.error_other(format!("Unexpected keyword '{}'", &normalized_word))
                         })?;
                 self.add(keyword_token, Some(unwrapped_word))?;
                 return Ok(());
@@ -347,7 +746,7 @@ impl<'a> TokenizerState<'a> {
 
         match self.settings.single_tokens.get(&self.current_char) {
             Some(token_type) => self.add(*token_type, Some(self.current_char.to_string())),
-            None => self.scan_var(),
+            None => self.scan_var_or_confusable(),
         }
     }
 
@@ -367,7 +766,7 @@ impl<'a> TokenizerState<'a> {
             let comment_end_size = comment_end.len();
 
             while !self.is_end {
-                if self.chars(comment_end_size) == *comment_end {
+                if self.sql[self.current_char_start..].starts_with(comment_end.as_str()) {
                     comment_count -= 1;
                     if comment_count == 0 {
                         break;
@@ -379,7 +778,7 @@ impl<'a> TokenizerState<'a> {
                 // Nested comments are allowed by some dialects, e.g. databricks, duckdb, postgres
                 if self.settings.nested_comments
                     && !self.is_end
-                    && self.chars(comment_start_size) == *comment_start
+                    && self.sql[self.current_char_start..].starts_with(comment_start)
                 {
                     self.advance(comment_start_size as isize)?;
                     comment_count += 1
@@ -424,10 +823,76 @@ impl<'a> TokenizerState<'a> {
     }
 
     fn scan_string(&mut self, start: &String) -> Result<bool, TokenizerError> {
+        // A quote prefix like `r'..'`/`R'..'` (raw) or `n'..'`/`N'..'`
+        // (national) maps straight to a string-kind token type via
+        // `string_prefixes`, independent of the base-encoded (hex/bit) and
+        // heredoc handling `format_strings` covers below - it only
+        // dispatches when the prefix is immediately followed by a real
+        // quote delimiter, so a bare identifier starting with `r` isn't
+        // swallowed as a raw string. Recognized case-insensitively, same as
+        // the `format_strings` prefixes.
+        let uppercased_prefix;
+        let string_prefix_key = if self.settings.string_prefixes.contains_key(start) {
+            Some(start)
+        } else {
+            uppercased_prefix = start.to_uppercase();
+            if self.settings.string_prefixes.contains_key(&uppercased_prefix) {
+                Some(&uppercased_prefix)
+            } else {
+                None
+            }
+        };
+
+        if let Some(string_prefix_key) = string_prefix_key {
+            let prefix_kind = *self.settings.string_prefixes.get(string_prefix_key).unwrap();
+            // `string_prefixes` keys can be more than one char (e.g. a
+            // `rb'..'` byte-raw-string prefix), so look at whatever
+            // immediately follows the *whole* prefix rather than assuming
+            // it's exactly one char and using `peek_char` (which is only
+            // one char past `current_char`, the prefix's first char).
+            let prefix_end = self.current_char_start + start.len();
+            let quote_char = if prefix_end < self.size {
+                Some(self.char_at(prefix_end)?)
+            } else {
+                None
+            };
+            let quote_end = quote_char.and_then(|c| self.settings.quotes.get(&c.to_string()));
+            if let Some(end) = quote_end.cloned() {
+                // `advance` past the prefix *and* the opening quote it was
+                // tested against above - `extract_string` expects to start
+                // just after the delimiter, same as the plain-quote path
+                // below.
+                self.advance(start.len() as isize + 1)?;
+                let text = self.extract_string(
+                    &end,
+                    false,
+                    prefix_kind == self.token_types.raw_string,
+                    true,
+                )?;
+                self.add(prefix_kind, Some(text))?;
+                return Ok(true);
+            }
+        }
+
+        // Quote prefixes like `x'..'`/`X'..'` or `e'..'`/`E'..'` are
+        // recognized regardless of case, so fall back to the upper-cased
+        // spelling when the settings map was keyed that way.
+        let uppercased_start;
+        let format_string_key = if self.settings.format_strings.contains_key(start) {
+            Some(start)
+        } else {
+            uppercased_start = start.to_uppercase();
+            if self.settings.format_strings.contains_key(&uppercased_start) {
+                Some(&uppercased_start)
+            } else {
+                None
+            }
+        };
+
         let (base, token_type, end) = if let Some(end) = self.settings.quotes.get(start) {
             (None, self.token_types.string, end.clone())
-        } else if self.settings.format_strings.contains_key(start) {
-            let (ref end, token_type) = self.settings.format_strings.get(start).unwrap();
+        } else if let Some(format_string_key) = format_string_key {
+            let (ref end, token_type) = self.settings.format_strings.get(format_string_key).unwrap();
 
             if *token_type == self.token_types.hex_string {
                 (Some(16), *token_type, end.clone())
@@ -469,10 +934,7 @@ impl<'a> TokenizerState<'a> {
 
         if let Some(b) = base {
             if u128::from_str_radix(&text, b).is_err() {
-                return self.error_result(format!(
-                    "Numeric string contains invalid characters from {}:{}",
-                    self.line, self.start
-                ));
+                return Err(self.error_invalid_number(text));
             }
         }
 
@@ -588,6 +1050,78 @@ impl<'a> TokenizerState<'a> {
         }
     }
 
+    // When `scan_keyword` falls through here, `current_char` is neither a
+    // keyword/comment/string starter nor a `single_tokens` entry. If it's
+    // also a Unicode lookalike for one of those (a pasted fullwidth paren, a
+    // curly quote, ...), scanning it as a bare identifier character just
+    // defers the failure to the parser with a much less useful error. With
+    // `dialect_settings.normalize_confusables` set, or while
+    // `tokenize_recover`ing, substitute the ASCII character it's mistaken
+    // for and dispatch on that instead - except quote lookalikes, which are
+    // scanned as ordinary identifier characters (with a diagnostic only
+    // while recovering; see the comment below on why they can't be
+    // dispatched as strings).
+    fn scan_var_or_confusable(&mut self) -> Result<(), TokenizerError> {
+        if self.is_alphabetic_or_underscore(self.current_char) {
+            return self.scan_var();
+        }
+
+        let ascii = match confusable_ascii_for(self.current_char) {
+            Some(ascii) => ascii,
+            None => return self.scan_var(),
+        };
+
+        if !self.recovering && !self.dialect_settings.normalize_confusables {
+            return self.scan_var();
+        }
+
+        // Quote lookalikes (curly "smart" quotes, ...) are deliberately not
+        // dispatched as strings here: `scan_string` would advance past this
+        // (confusable) opening quote and then have `extract_string` hunt for
+        // the *ASCII* closing delimiter, but pasted text almost always
+        // closes with the same confusable quote, not the ASCII one - so the
+        // scan would run to EOF and raise `UnterminatedDelimiter` instead of
+        // actually recovering anything. Single-character substitutions
+        // (parens, comma, semicolon, ...) don't have this problem since
+        // there's no closing delimiter to mismatch.
+        if self.settings.quotes.contains_key(&ascii.to_string()) {
+            if self.recovering {
+                self.recovered_errors.push(self.error_other(format!(
+                    "Unicode lookalike quote character '{}' (did you mean '{}'?) is not supported as a string delimiter",
+                    self.current_char, ascii
+                )));
+            }
+            return self.scan_var();
+        }
+
+        let original = self.current_char;
+        let dispatched = if let Some(token_type) =
+            self.settings.single_tokens.get(&ascii).copied()
+        {
+            self.add(token_type, Some(original.to_string()))?;
+            true
+        } else {
+            false
+        };
+
+        if !dispatched {
+            return self.scan_var();
+        }
+
+        if self.recovering {
+            // The character was already dispatched as its ASCII lookalike
+            // and a token emitted for it - surface the diagnostic without
+            // failing the scan, or `tokenize_recover`'s resync would skip
+            // past whatever legitimate token follows.
+            self.recovered_errors.push(self.error_other(format!(
+                "Unicode lookalike character '{}' (did you mean '{}'?)",
+                original, ascii
+            )));
+        }
+
+        Ok(())
+    }
+
     fn scan_var(&mut self) -> Result<(), TokenizerError> {
         loop {
             let peek_char = if !self.peek_char.is_whitespace() {
@@ -621,6 +1155,9 @@ impl<'a> TokenizerState<'a> {
     fn scan_identifier(&mut self, identifier_end: &str) -> Result<(), TokenizerError> {
         self.advance(1)?;
         let text = self.extract_string(identifier_end, true, false, true)?;
+        if text.is_empty() {
+            return Err(self.error_invalid_identifier("Identifier cannot be empty".to_string()));
+        }
         self.add(self.token_types.identifier, Some(text))
     }
 
@@ -644,6 +1181,61 @@ impl<'a> TokenizerState<'a> {
         };
 
         loop {
+            // Quoted identifiers only ever escape by doubling the delimiter
+            // (e.g. `"a""b"`), handled below by `escapes`/`combined_identifier_escapes`
+            // regardless of this branch; a backslash inside one is just a
+            // literal backslash in every dialect this tokenizer targets.
+            // C-style decoding (`\n`, `\xHH`, `\uXXXX`, ...) is therefore
+            // intentionally scoped to string literals, not quoted
+            // identifiers, so it's decoded here only outside of
+            // `use_identifier_escapes`. The per-dialect on/off switch for
+            // that decoding is `dialect_settings.decode_string_escapes`
+            // itself (added alongside `decode_escape_sequence` below); this
+            // guard only narrows *where* it applies, not whether it's
+            // configurable.
+            if !raw_string
+                && !use_identifier_escapes
+                && self.dialect_settings.decode_string_escapes
+                && self.current_char == '\\'
+            {
+                let escape_start = self.current_char_start;
+                match self.decode_escape_sequence() {
+                    Ok(decoded) => {
+                        text.push(decoded);
+                        self.advance(1)?;
+                        continue;
+                    }
+                    Err(e) => {
+                        if !self.recovering {
+                            return Err(e);
+                        }
+                        // Flag the malformed escape but keep the token alive:
+                        // carry everything decode_escape_sequence consumed
+                        // while probing the escape through literally (not
+                        // just the backslash), so the reconstructed text
+                        // matches the original source, and resume scanning
+                        // from wherever the failed decode left off.
+                        self.recovered_errors.push(e);
+                        text.push_str(&self.sql[escape_start..self.cursor.offset]);
+                        // A truncated escape can leave us sitting on the
+                        // last char of the input with nothing consumed (a
+                        // lone trailing backslash never even advances past
+                        // itself) - advancing would raise `UnexpectedEof`,
+                        // and `continue`-ing without advancing would re-enter
+                        // this same branch forever. End the token here the
+                        // same way the loop's own EOF handling below would.
+                        if self.is_end {
+                            if !raise_unmatched {
+                                return Ok(text);
+                            }
+                            return Err(self.error_unterminated_delimiter(delimiter));
+                        }
+                        self.advance(1)?;
+                        continue;
+                    }
+                }
+            }
+
             if !raw_string
                 && !self.dialect_settings.unescaped_sequences.is_empty()
                 && !self.peek_char.is_whitespace()
@@ -676,18 +1268,20 @@ impl<'a> TokenizerState<'a> {
                         text.push(self.current_char);
                         text.push(self.peek_char);
                     }
-                    if self.current + 1 < self.size {
+                    if self.cursor.offset + self.peek_char.len_utf8() < self.size {
                         self.advance(2)?;
                     } else {
-                        return self.error_result(format!(
-                            "Missing {} from {}:{}",
-                            delimiter, self.line, self.current
-                        ));
+                        return Err(self.error_unterminated_delimiter(delimiter));
                     }
                     continue;
                 }
             }
-            if self.chars(delimiter.len()) == delimiter {
+            // `cursor.rest` starts one char *past* `current_char` (see its
+            // field comment), so matching the delimiter against it would
+            // skip `current_char` itself, silently dropping the last
+            // character of every delimited string/identifier. Match from
+            // `current_char_start` instead, which includes it.
+            if self.sql[self.current_char_start..].starts_with(delimiter) {
                 if delimiter.len() > 1 {
                     self.advance((delimiter.len() - 1) as isize)?;
                 }
@@ -699,23 +1293,162 @@ impl<'a> TokenizerState<'a> {
                     return Ok(text);
                 }
 
-                return self.error_result(format!(
-                    "Missing {} from {}:{}",
-                    delimiter, self.line, self.current
-                ));
+                return Err(self.error_unterminated_delimiter(delimiter));
             }
 
-            let current = self.current - 1;
+            text.push(self.current_char);
             self.advance(1)?;
-            text.push_str(
-                &self.sql[current..self.current - 1]
-                    .iter()
-                    .collect::<String>(),
-            );
         }
         Ok(text)
     }
 
+    // Decodes a C-style escape sequence for `dialect_settings.decode_string_escapes`.
+    // `self.current_char` is the introducing backslash on entry; on success
+    // the cursor ends up sitting on the escape's last character, matching
+    // the convention the rest of `extract_string` uses to advance past
+    // whatever it just consumed.
+    fn decode_escape_sequence(&mut self) -> Result<char, TokenizerError> {
+        if self.is_end {
+            return Err(self.error_invalid_escape("Truncated escape sequence at end of input".to_string()));
+        }
+        self.advance(1)?;
+
+        match self.current_char {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            'b' => Ok('\u{8}'),
+            'f' => Ok('\u{C}'),
+            '0' => Ok('\0'),
+            '\\' => Ok('\\'),
+            '\'' => Ok('\''),
+            '"' => Ok('"'),
+            'x' => self.decode_fixed_hex_escape(2),
+            'u' => self.decode_unicode_escape(),
+            '1'..='7' => self.decode_octal_escape(),
+            c => Err(self.error_invalid_escape(format!("Invalid escape sequence '\\{}'", c))),
+        }
+    }
+
+    // Reads exactly `digits` hex digits, each one a further `advance(1)`, and
+    // decodes them as a Unicode scalar value.
+    fn decode_fixed_hex_escape(&mut self, digits: usize) -> Result<char, TokenizerError> {
+        let mut value: u32 = 0;
+        for _ in 0..digits {
+            if self.is_end {
+                return Err(self.error_invalid_escape("Truncated escape sequence at end of input".to_string()));
+            }
+            self.advance(1)?;
+            let digit = self.current_char.to_digit(16).ok_or_else(|| {
+                self.error_invalid_escape(format!(
+                    "Invalid hex digit '{}' in escape sequence",
+                    self.current_char
+                ))
+            })?;
+            value = value * 16 + digit;
+        }
+        char::from_u32(value).ok_or_else(|| {
+            self.error_invalid_escape(format!(
+                "'\\x{:x}' is not a valid Unicode scalar value",
+                value
+            ))
+        })
+    }
+
+    // Handles both `\uHHHH` (exactly four hex digits) and `\u{H..H}` (one to
+    // six hex digits between braces), following rustc's unescape rules.
+    fn decode_unicode_escape(&mut self) -> Result<char, TokenizerError> {
+        if self.is_end {
+            return Err(self.error_invalid_escape("Truncated \\u escape at end of input".to_string()));
+        }
+        self.advance(1)?;
+
+        if self.current_char != '{' {
+            return self.decode_fixed_hex_escape_from_current(4);
+        }
+
+        let mut value: u32 = 0;
+        let mut digit_count = 0;
+        loop {
+            if self.is_end {
+                return Err(self.error_invalid_escape("Truncated \\u{...} escape at end of input".to_string()));
+            }
+            self.advance(1)?;
+            if self.current_char == '}' {
+                break;
+            }
+            if digit_count == 6 {
+                return Err(self
+                    .error_invalid_escape("'\\u{...}' escape has too many hex digits (max 6)".to_string()));
+            }
+            let digit = self.current_char.to_digit(16).ok_or_else(|| {
+                self.error_invalid_escape(format!(
+                    "Invalid hex digit '{}' in '\\u{{...}}' escape",
+                    self.current_char
+                ))
+            })?;
+            value = value * 16 + digit;
+            digit_count += 1;
+        }
+
+        if digit_count == 0 {
+            return Err(self.error_invalid_escape("Empty '\\u{}' escape".to_string()));
+        }
+
+        char::from_u32(value).ok_or_else(|| {
+            self.error_invalid_escape(format!(
+                "'\\u{{{:x}}}' is not a valid Unicode scalar value (out of range or an unpaired surrogate)",
+                value
+            ))
+        })
+    }
+
+    // Like `decode_fixed_hex_escape`, but the first of the `digits` hex
+    // digits is already sitting in `current_char` rather than needing an
+    // `advance(1)` to reach it.
+    fn decode_fixed_hex_escape_from_current(&mut self, digits: usize) -> Result<char, TokenizerError> {
+        let mut value = self.current_char.to_digit(16).ok_or_else(|| {
+            self.error_invalid_escape(format!(
+                "Invalid hex digit '{}' in '\\u' escape",
+                self.current_char
+            ))
+        })?;
+        for _ in 0..digits - 1 {
+            if self.is_end {
+                return Err(self.error_invalid_escape("Truncated \\u escape at end of input".to_string()));
+            }
+            self.advance(1)?;
+            let digit = self.current_char.to_digit(16).ok_or_else(|| {
+                self.error_invalid_escape(format!(
+                    "Invalid hex digit '{}' in '\\u' escape",
+                    self.current_char
+                ))
+            })?;
+            value = value * 16 + digit;
+        }
+        char::from_u32(value).ok_or_else(|| {
+            self.error_invalid_escape(format!(
+                "'\\u{:04x}' is not a valid Unicode scalar value (out of range or an unpaired surrogate)",
+                value
+            ))
+        })
+    }
+
+    // Optional `\NNN` octal escape (1-3 digits); `current_char` is already
+    // the first digit on entry.
+    fn decode_octal_escape(&mut self) -> Result<char, TokenizerError> {
+        let mut value = self.current_char.to_digit(8).unwrap();
+        for _ in 0..2 {
+            if self.is_end || !self.peek_char.is_digit(8) {
+                break;
+            }
+            self.advance(1)?;
+            value = value * 8 + self.current_char.to_digit(8).unwrap();
+        }
+        char::from_u32(value)
+            .ok_or_else(|| self.error_invalid_escape(format!("Octal escape '\\{:o}' is not a valid character", value)))
+    }
+
     fn is_alphabetic_or_underscore(&self, name: char) -> bool {
         name.is_alphabetic() || name == '_'
     }
@@ -748,14 +1481,107 @@ impl<'a> TokenizerState<'a> {
         Ok(self.text())
     }
 
-    fn error(&self, message: String) -> TokenizerError {
-        let start = max((self.current as isize) - 50, 0);
-        let end = min(self.current + 50, self.size - 1);
-        let context = self.sql[start as usize..end].iter().collect::<String>();
-        TokenizerError { message, context }
+    fn context(&self) -> String {
+        let offset = self.cursor.offset;
+        let start = self.sql[..offset]
+            .char_indices()
+            .rev()
+            .nth(49)
+            .map_or(0, |(i, _)| i);
+        let end = self.sql[offset..]
+            .char_indices()
+            .nth(50)
+            .map_or(self.size, |(i, _)| offset + i);
+
+        self.sql[start..end].to_string()
+    }
+
+    // The byte range of the token currently being scanned (`self.start` up
+    // to the cursor) plus the cursor's current line/column, for attaching to
+    // a diagnostic raised while scanning it.
+    fn span(&self) -> Span {
+        Span {
+            start: self.start,
+            end: self.cursor.offset,
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn error_other(&self, message: String) -> TokenizerError {
+        TokenizerError::Other {
+            message,
+            span: self.span(),
+            context: self.context(),
+        }
     }
 
-    fn error_result<T>(&self, message: String) -> Result<T, TokenizerError> {
-        Err(self.error(message))
+    fn error_unexpected_eof(&self) -> TokenizerError {
+        TokenizerError::UnexpectedEof {
+            span: self.span(),
+            context: self.context(),
+        }
+    }
+
+    fn error_unterminated_delimiter(&self, delimiter: &str) -> TokenizerError {
+        TokenizerError::UnterminatedDelimiter {
+            delimiter: delimiter.to_string(),
+            span: self.span(),
+            context: self.context(),
+        }
+    }
+
+    fn error_invalid_number(&self, text: String) -> TokenizerError {
+        TokenizerError::InvalidNumber {
+            text,
+            span: self.span(),
+            context: self.context(),
+        }
+    }
+
+    fn error_invalid_escape(&self, reason: String) -> TokenizerError {
+        TokenizerError::InvalidEscape {
+            reason,
+            span: self.span(),
+            context: self.context(),
+        }
+    }
+
+    fn error_invalid_identifier(&self, reason: String) -> TokenizerError {
+        TokenizerError::InvalidIdentifier {
+            reason,
+            span: self.span(),
+            context: self.context(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn tokenize_recover_trailing_backslash_does_not_hang() {
+        let mut quotes = HashMap::new();
+        quotes.insert("'".to_string(), "'".to_string());
+
+        let settings = TokenizerSettings {
+            quotes,
+            string_escapes: ['\\'].into_iter().collect(),
+            ..Default::default()
+        };
+        let dialect_settings = TokenizerDialectSettings {
+            decode_string_escapes: true,
+            ..Default::default()
+        };
+        let tokenizer = Tokenizer::new(settings, TokenTypeSettings::default());
+
+        // A lone trailing backslash is a truncated escape with nothing left
+        // to decode. Recovery must report it and terminate instead of
+        // looping forever re-matching the same unconsumed backslash.
+        let (_, errors) = tokenizer.tokenize_recover("'\\", &dialect_settings);
+
+        assert!(!errors.is_empty());
     }
 }